@@ -1,12 +1,12 @@
+mod x11;
+mod wayland;
+
 use structopt::StructOpt;
-use x11::xlib;
-use x11::xrandr;
-use x11::xinput2;
-use std::ptr;
 use std::env;
 use std::ffi::CString;
-use std::mem::MaybeUninit;
 use std::ffi::CStr;
+use std::collections::HashMap;
+use std::error::Error;
 use nix::unistd;
 use nix::sys;
 use std::sync::atomic::AtomicBool;
@@ -15,10 +15,14 @@ use configparser::ini::Ini;
 use std::thread;
 use std::time;
 
-static RUNNING: AtomicBool = AtomicBool::new(true);
+use crate::wayland::WaylandSource;
+use crate::x11::X11Source;
+
+pub(crate) static RUNNING: AtomicBool = AtomicBool::new(true);
+pub(crate) static RELOAD: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, StructOpt)]
-struct Opts {
+pub(crate) struct Opts {
 	#[structopt(long, value_name = "CMD", help = "Top left corner command")]
 	topleft: Option<String>,
 
@@ -54,10 +58,16 @@ struct Opts {
 
 	#[structopt(long, short, value_name = "N", help = "Delay command execution for N milliseconds")]
 	delay: Option<u64>,
+
+	#[structopt(long, value_name = "PX", help = "Corner trigger size in logical pixels")]
+	corner_size: Option<u32>,
+
+	#[structopt(long, value_name = "PX", help = "Edge trigger margin in logical pixels")]
+	edge_margin: Option<u32>,
 }
 
-#[derive(Debug)]
-struct Commands {
+#[derive(Debug, Default)]
+pub(crate) struct Commands {
 	topleft: Option<String>,
 	topright: Option<String>,
 	bottomright: Option<String>,
@@ -68,8 +78,16 @@ struct Commands {
 	bottom: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
-enum Edge {
+/// The global `[commands]` bindings plus any per-monitor `[monitor.NAME]`
+/// overrides, keyed by the RandR/xdg-output monitor name (e.g. `HDMI-1`).
+#[derive(Debug, Default)]
+pub(crate) struct Bindings {
+	global: Commands,
+	monitors: HashMap<String, Commands>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Edge {
 	TOPLEFT,
 	TOPRIGHT,
 	BOTTOMRIGHT,
@@ -81,11 +99,43 @@ enum Edge {
 	NONE,
 }
 
+/// A pointer position reported by a `PointerSource`, together with the
+/// bounds and identity of the monitor it currently sits on.
+#[derive(Debug, Clone)]
+pub(crate) struct PointerUpdate {
+	pub(crate) x: i32,
+	pub(crate) y: i32,
+	pub(crate) xmax: i32,
+	pub(crate) ymax: i32,
+	/// HiDPI scale factor of the monitor, 1.0 at the toolkit-standard 96 DPI.
+	pub(crate) scale: f64,
+	/// RandR/xdg-output monitor name (e.g. `HDMI-1`), when known.
+	pub(crate) monitor: Option<String>,
+}
+
+/// A source of global pointer motion, abstracting over the display server.
+///
+/// Implementations block until the next raw pointer motion (or a shutdown
+/// or config reload request, which yields `None`) and report where the
+/// pointer is.
+pub(crate) trait PointerSource {
+	fn next_motion(&mut self) -> Result<Option<PointerUpdate>, Box<dyn Error>>;
+
+	/// Reports the current pointer position, as far as the backend knows
+	/// it. Used to confirm the pointer is still in an edge after the
+	/// configured delay.
+	fn query_pointer(&self) -> Result<(i32, i32), Box<dyn Error>>;
+}
+
 fn sighandler() {
 	RUNNING.store(false, Ordering::Relaxed);
 }
 
-fn point_in_rect(x: i32, y: i32, rect: (i32, i32, i32, i32)) -> bool
+fn reload_handler() {
+	RELOAD.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn point_in_rect(x: i32, y: i32, rect: (i32, i32, i32, i32)) -> bool
 {
 	let (rx, ry, rw, rh) = rect;
 
@@ -96,89 +146,42 @@ fn point_in_rect(x: i32, y: i32, rect: (i32, i32, i32, i32)) -> bool
 	return false;
 }
 
-fn pointer_in_monitor(x: i32, y: i32, nmonitors: i32, monitorinfo: *const xrandr::XRRMonitorInfo) -> i32
-{
-	for i in 0..nmonitors {
-		let rect = unsafe {
-			((*monitorinfo.offset(i as isize)).x,
-			 (*monitorinfo.offset(i as isize)).y,
-			 (*monitorinfo.offset(i as isize)).width,
-			 (*monitorinfo.offset(i as isize)).height)
-		};
-
-		if point_in_rect(x, y, rect) {
-			return i;
-		}
-	}
-
-	return -1;
-}
-
-fn get_xymax(x: i32, y: i32, xmax: &mut i32, ymax: &mut i32, display: *mut xlib::Display, nmonitors: i32, monitorinfo: *const xrandr::XRRMonitorInfo)
-{
-	unsafe {
-		*xmax = xlib::XDisplayWidth(display, xlib::XDefaultScreen(display)) - 1;
-		*ymax = xlib::XDisplayHeight(display, xlib::XDefaultScreen(display)) - 1;
-	}
-
-	if nmonitors == 1 {
-		return;
-	}
-
-	let i = pointer_in_monitor(x, y, nmonitors, monitorinfo);
-	if i < 0 {
-		panic!("pointer_in_mointor failed");
-	}
-
-	unsafe {
-		let w = (*monitorinfo.offset(i as isize)).width;
-		let xoff = (*monitorinfo.offset(i as isize)).x;
-
-		if xoff + w <= *xmax {
-			*xmax = xoff + w - 1;
-		}
-
-		let h = (*monitorinfo.offset(i as isize)).height;
-		let yoff = (*monitorinfo.offset(i as isize)).y;
-
-		if yoff + h <= *ymax {
-			*ymax = yoff + h - 1;
-		}
-	}
-}
-
-fn in_edge(x: i32, y: i32, xmax: i32, ymax: i32, offset: i32) -> Edge
+/// Classifies `(x, y)` as a corner/edge hit. `corner_size` and
+/// `edge_margin` are already scaled to physical pixels for the monitor
+/// the pointer is on; `offset` keeps the dead zone between the corner
+/// and edge regions the same as before.
+fn in_edge(x: i32, y: i32, xmax: i32, ymax: i32, corner_size: i32, edge_margin: i32, offset: i32) -> Edge
 {
-	if x == 0 && y == 0 {
+	if x <= corner_size && y <= corner_size {
 		return Edge::TOPLEFT;
 	}
-	if x == xmax && y == 0 {
+	if x >= xmax - corner_size && y <= corner_size {
 		return Edge::TOPRIGHT;
 	}
-	if x == xmax && y == ymax {
+	if x >= xmax - corner_size && y >= ymax - corner_size {
 		return Edge::BOTTOMRIGHT;
 	}
-	if x == 0 && y == ymax {
+	if x <= corner_size && y >= ymax - corner_size {
 		return Edge::BOTTOMLEFT;
 	}
-	if x == 0 && y > offset && y < ymax - offset {
+	if x <= edge_margin && y > offset && y < ymax - offset {
 		return Edge::LEFT;
 	}
-	if y == 0 && x > offset && x < xmax - offset {
+	if y <= edge_margin && x > offset && x < xmax - offset {
 		return Edge::TOP;
 	}
-	if x == xmax && y > offset && y < ymax - offset {
+	if x >= xmax - edge_margin && y > offset && y < ymax - offset {
 		return Edge::RIGHT;
 	}
-	if y == ymax && x > offset && x < xmax - offset {
+	if y >= ymax - edge_margin && x > offset && x < xmax - offset {
 		return Edge::BOTTOM;
 	}
 	return Edge::NONE;
 }
 
-fn run(opts: &Opts, edge: Edge, cmds: &Commands)
+fn command_field(cmds: &Commands, edge: &Edge) -> &Option<String>
 {
-	let cmd = match edge {
+	match edge {
 		Edge::TOPLEFT => &cmds.topleft,
 		Edge::TOPRIGHT => &cmds.topright,
 		Edge::BOTTOMRIGHT => &cmds.bottomright,
@@ -187,8 +190,33 @@ fn run(opts: &Opts, edge: Edge, cmds: &Commands)
 		Edge::TOP => &cmds.top,
 		Edge::RIGHT => &cmds.right,
 		Edge::BOTTOM => &cmds.bottom,
-		_ => &None,
-	};
+		Edge::NONE => &None,
+	}
+}
+
+/// Picks the command for `edge`, preferring the bindings of `monitor` (if
+/// it has a `[monitor.NAME]` section and that edge is bound there) and
+/// falling back to the global `[commands]` section otherwise. `monitor`
+/// names are matched case-insensitively, since `configparser` lowercases
+/// section names (`[monitor.HDMI-1]` is stored as `hdmi-1`) while the
+/// RandR name atom keeps its original case (`HDMI-1`).
+fn resolve_command<'a>(bindings: &'a Bindings, monitor: Option<&str>, edge: &Edge) -> &'a Option<String>
+{
+	if let Some(name) = monitor {
+		if let Some(cmds) = bindings.monitors.get(&name.to_lowercase()) {
+			let cmd = command_field(cmds, edge);
+			if cmd.is_some() {
+				return cmd;
+			}
+		}
+	}
+
+	command_field(&bindings.global, edge)
+}
+
+fn run(opts: &Opts, edge: Edge, bindings: &Bindings, monitor: Option<&str>)
+{
+	let cmd = resolve_command(bindings, monitor, &edge);
 
 	if opts.debug {
 		println!("{:?}: {:?}", edge, cmd);
@@ -223,42 +251,103 @@ fn run(opts: &Opts, edge: Edge, cmds: &Commands)
 	}
 }
 
-fn query_pointer(display: *mut xlib::Display, window: xlib::Window) -> (i32, i32)
-{
-	let mut root_ret: u64 = 0;
-	let mut child_ret: u64 = 0;
-	let mut x: i32 = 0;
-	let mut y: i32 = 0;
-	let mut winx_ret: i32 = 0;
-	let mut winy_ret: i32 = 0;
-	let mut mask_ret: u32 = 0;
+/// Drives edge/corner detection from a stream of pointer motions, no
+/// matter which `PointerSource` they came from, and dispatches the
+/// matching command.
+struct EdgeActor<'a> {
+	opts: &'a Opts,
+	bindings: Bindings,
+	delay: u64,
+	corner_size: u32,
+	edge_margin: u32,
+	// The zone (or Edge::NONE) the pointer was last classified in, so a
+	// pointer that lingers or jitters within the same hot zone doesn't
+	// re-fire on every motion.
+	last_edge: Edge,
+}
 
-	unsafe {
-		xlib::XQueryPointer(display,
-				    window,
-				    &mut root_ret,
-				    &mut child_ret,
-				    &mut x,
-				    &mut y,
-				    &mut winx_ret,
-				    &mut winy_ret,
-				    &mut mask_ret);
+impl<'a> EdgeActor<'a> {
+	fn new(opts: &'a Opts, bindings: Bindings, delay: u64, corner_size: u32, edge_margin: u32) -> Self
+	{
+		EdgeActor { opts, bindings, delay, corner_size, edge_margin, last_edge: Edge::NONE }
 	}
 
-	return (x, y);
+	/// Swaps in freshly loaded bindings, e.g. after a SIGHUP config reload.
+	fn reload_bindings(&mut self, bindings: Bindings)
+	{
+		self.bindings = bindings;
+	}
+
+	fn on_motion(&mut self, update: PointerUpdate, source: &dyn PointerSource) -> Result<(), Box<dyn Error>>
+	{
+		let PointerUpdate { x, y, xmax, ymax, scale, monitor } = update;
+
+		if self.opts.debug {
+			println!("{} {}", x, y);
+		}
+
+		// Scale the configured logical-pixel geometry to this monitor's DPI
+		let corner_size: i32 = ((self.corner_size as f64) * scale).round() as i32;
+		let edge_margin: i32 = ((self.edge_margin as f64) * scale).round() as i32;
+
+		// Specifies the "hot" zones
+		let offset: i32 = ((ymax as f64) * 0.25) as i32;
+
+		let edge = in_edge(x, y, xmax, ymax, corner_size, edge_margin, offset);
+
+		// Make sure we run a command only once per zone hit: fire on the
+		// transition into a new edge/corner, then suppress repeats while
+		// the pointer lingers or jitters within the same zone, until it
+		// leaves the zone entirely (Edge::NONE) and can re-arm.
+		if edge == self.last_edge {
+			return Ok(());
+		}
+		self.last_edge = edge;
+
+		if edge != Edge::NONE {
+			if self.opts.debug {
+				println!("delay: {}", self.delay);
+			}
+
+			// Apply delay
+			thread::sleep(time::Duration::from_millis(self.delay));
+
+			// Run the command if the pointer is still in the edge
+			let (x, y) = source.query_pointer()?;
+			if edge == in_edge(x, y, xmax, ymax, corner_size, edge_margin, offset) {
+				run(self.opts, edge, &self.bindings, monitor.as_deref());
+			} else {
+				// Pointer already left before the delay elapsed; let
+				// the next hit into this zone fire normally.
+				self.last_edge = Edge::NONE;
+			}
+		}
+
+		Ok(())
+	}
 }
 
-fn main()
+fn commands_from_section(cfg: &Ini, section: &str) -> Commands
 {
-	let opts = Opts::from_args();
-
-	// Set delay
-	let default_delay: u64 = 0;
-	let max_delay: u64 = 1000;
-	let delay: u64 = opts.delay.unwrap_or(default_delay).min(max_delay);
+	Commands {
+		topleft: cfg.get(section, "topleft"),
+		topright: cfg.get(section, "topright"),
+		bottomright: cfg.get(section, "bottomright"),
+		bottomleft: cfg.get(section, "bottomleft"),
+		left: cfg.get(section, "left"),
+		top: cfg.get(section, "top"),
+		right: cfg.get(section, "right"),
+		bottom: cfg.get(section, "bottom"),
+	}
+}
 
-	// Set commands from arguments
-	let mut cmds = Commands {
+/// Builds `Bindings` from the CLI arguments, overlaid with the
+/// `[commands]` section of `edges.conf` and any per-monitor
+/// `[monitor.NAME]` sections when `--config` is set. Also used to
+/// re-read the config file on a SIGHUP reload.
+fn load_bindings(opts: &Opts) -> Result<Bindings, Box<dyn Error>>
+{
+	let mut global = Commands {
 		topleft: opts.topleft.clone(),
 		topright: opts.topright.clone(),
 		bottomright: opts.bottomright.clone(),
@@ -268,179 +357,120 @@ fn main()
 		right: opts.right.clone(),
 		bottom: opts.bottom.clone(),
 	};
+	let mut monitors = HashMap::new();
 
-	// Load commands from file
 	if opts.config {
 		let mut cfg = Ini::new();
-		let mut path = dirs::config_dir().unwrap();
+		let mut path = dirs::config_dir().ok_or("could not determine config directory")?;
 		path.push("edges.conf");
-		if let Err(err) = cfg.load(path) {
-			panic!("{}", err);
-		}
-		cmds.topleft = cfg.get("commands", "topleft");
-		cmds.topright = cfg.get("commands", "topright");
-		cmds.bottomright = cfg.get("commands", "bottomright");
-		cmds.bottomleft = cfg.get("commands", "bottomleft");
-		cmds.left = cfg.get("commands", "left");
-		cmds.top = cfg.get("commands", "top");
-		cmds.right = cfg.get("commands", "right");
-		cmds.bottom = cfg.get("commands", "bottom");
-	}
+		cfg.load(path)?;
 
-	// Check if we run on Wayland
-	if let Ok(_) = env::var("WAYLAND_DISPLAY") {
-		panic!("Global pointer query not supported on Wayland");
-	}
-
-	unsafe {
-		// Catch signals
-		libc::signal(libc::SIGINT, sighandler as usize);
-		libc::signal(libc::SIGTERM, sighandler as usize);
-		libc::signal(libc::SIGHUP, sighandler as usize);
+		global = commands_from_section(&cfg, "commands");
 
-		// Open display
-		let display = xlib::XOpenDisplay(ptr::null());
-		if display.is_null() {
-			panic!("XOpenDisplay failed");
+		for section in cfg.sections() {
+			if let Some(name) = section.strip_prefix("monitor.") {
+				monitors.insert(name.to_string(), commands_from_section(&cfg, &section));
+			}
 		}
+	}
 
-		let window = xlib::XDefaultRootWindow(display);
-
-		// Query XInput2
-		let mut major_opcode: i32 = 0;
-		let mut first_event: i32 = 0;
-		let mut first_error: i32 = 0;
-		let c_str = CString::new("XInputExtension").unwrap();
+	Ok(Bindings { global, monitors })
+}
 
-		if xlib::XQueryExtension(display,
-					 c_str.as_ptr(),
-					 &mut major_opcode,
-					 &mut first_event,
-					 &mut first_error) == xlib::False {
-			panic!("Failed to query XInputExtension");
-		}
+fn main() -> Result<(), Box<dyn Error>>
+{
+	let opts = Opts::from_args();
 
-		// Query Xrandr
-		let mut have_randr_1_5: bool = false;
-		let mut event_base: i32 = 0;
-		let mut error_base: i32 = 0;
+	// Set delay
+	let default_delay: u64 = 0;
+	let max_delay: u64 = 1000;
+	let delay: u64 = opts.delay.unwrap_or(default_delay).min(max_delay);
 
-		if xrandr::XRRQueryExtension(display, &mut event_base, &mut error_base) == xlib::True {
-			let mut major: i32 = 0;
-			let mut minor: i32 = 0;
+	// Set trigger geometry, in logical pixels
+	let default_corner_size: u32 = 8;
+	let default_edge_margin: u32 = 4;
+	let mut corner_size: u32 = opts.corner_size.unwrap_or(default_corner_size);
+	let mut edge_margin: u32 = opts.edge_margin.unwrap_or(default_edge_margin);
 
-			xrandr::XRRQueryVersion(display, &mut major, &mut minor);
+	// Set commands from arguments and config file
+	let bindings = load_bindings(&opts)?;
 
-			if (major == 1 && minor >= 5) || major > 1 {
-				have_randr_1_5 = true;
-			}
-		}
+	// Geometry is only ever read at startup, not reloaded on SIGHUP
+	if opts.config {
+		let mut cfg = Ini::new();
+		let mut path = dirs::config_dir().ok_or("could not determine config directory")?;
+		path.push("edges.conf");
+		cfg.load(path)?;
 
-		if !have_randr_1_5 {
-			panic!("Xrandr >= 1.5 not available");
+		if let Ok(Some(v)) = cfg.getuint("geometry", "corner_size") {
+			corner_size = v as u32;
 		}
-
-		// Select raw motion events
-		let mut mask = [0u8; (xinput2::XI_LASTEVENT as usize + 7) / 8]; // wtf?
-		xinput2::XISetMask(&mut mask, xinput2::XI_RawMotion);
-
-		let mut event_mask = xinput2::XIEventMask {
-			deviceid: xinput2::XIAllMasterDevices,
-			mask_len: mask.len() as i32,
-			mask: &mut mask[0] as *mut u8,
-		};
-		xinput2::XISelectEvents(display, window, &mut event_mask, 1);
-
-		// Get monitors
-		let mut nmonitors: i32 = 0;
-		let monitorinfo = xrandr::XRRGetMonitors(display, window, xlib::True, &mut nmonitors);
-		if monitorinfo.is_null() {
-			panic!("XRRGetMonitors failed");
+		if let Ok(Some(v)) = cfg.getuint("geometry", "edge_margin") {
+			edge_margin = v as u32;
 		}
+	}
 
-		// prepare polling
-		let mut fds = libc::pollfd {
-			fd: xlib::XConnectionNumber(display),
-			events: libc::POLLIN,
-			revents: 0,
-		};
+	// Catch signals. SIGHUP reloads the config instead of shutting down.
+	unsafe {
+		libc::signal(libc::SIGINT, sighandler as usize);
+		libc::signal(libc::SIGTERM, sighandler as usize);
+		libc::signal(libc::SIGHUP, reload_handler as usize);
+	}
 
-		// Main loop
+	// Pick the backend matching the running session
+	let mut source: Box<dyn PointerSource> = if env::var("WAYLAND_DISPLAY").is_ok() {
+		Box::new(WaylandSource::new(corner_size.max(edge_margin))?)
+	} else {
+		Box::new(X11Source::new()?)
+	};
 
-		let mut oldx: i32 = 1;
-		let mut oldy: i32 = 1;
-		let mut xmax: i32 = 0;
-		let mut ymax: i32 = 0;
+	let mut actor = EdgeActor::new(&opts, bindings, delay, corner_size, edge_margin);
 
-		while RUNNING.load(Ordering::Relaxed) {
-			if xlib::XPending(display) == 0 {
-				continue;
+	while RUNNING.load(Ordering::Relaxed) {
+		if RELOAD.swap(false, Ordering::Relaxed) {
+			match load_bindings(&opts) {
+				Ok(bindings) => actor.reload_bindings(bindings),
+				Err(err) => eprintln!("Failed to reload configuration: {}", err),
 			}
+		}
 
-			let event = {
-				let mut event = MaybeUninit::uninit();
-				xlib::XNextEvent(display, event.as_mut_ptr());
-				event.assume_init()
-			};
-
-			let mut cookie: xlib::XGenericEventCookie = event.generic_event_cookie;
-			xlib::XGetEventData(display, &mut cookie);
-
-			// Was pointer moved?
-			if cookie.type_ == xlib::GenericEvent &&
-			   cookie.extension == major_opcode &&
-			   cookie.evtype == xinput2::XI_RawMotion {
-
-				let (x, y) = query_pointer(display, window);
-
-				if opts.debug {
-					println!("{} {}", x, y);
-				}
-
-				get_xymax(x, y, &mut xmax, &mut ymax, display, nmonitors, monitorinfo);
-
-				// Specifies the "hot" zones
-				let offset: i32 = ((ymax as f64) * 0.25) as i32;
-
-				// Make sure we run commands only once on edge hits
-				if (x == oldx && y == oldy) ||
-				   (x == oldx && y > offset && y < ymax - offset) ||
-				   (y == oldy && x > offset && x < xmax - offset) {
-					xlib::XFreeEventData(display, &mut cookie);
-					continue;
-				}
+		if let Some(update) = source.next_motion()? {
+			actor.on_motion(update, source.as_ref())?;
+		}
+	}
 
-				let edge = in_edge(x, y, xmax, ymax, offset);
+	Ok(())
+}
 
-				if edge != Edge::NONE {
-					if opts.debug {
-						println!("delay: {}", delay);
-					}
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-					// Apply delay
-					thread::sleep(time::Duration::from_millis(delay));
+	#[test]
+	fn resolve_command_matches_monitor_name_case_insensitively()
+	{
+		let mut monitors = HashMap::new();
+		monitors.insert("hdmi-1".to_string(), Commands { topleft: Some("laptop-menu".to_string()), ..Default::default() });
+		let bindings = Bindings { global: Commands { topleft: Some("global-menu".to_string()), ..Default::default() }, monitors };
 
-					// Run the command if the pointer is still in the edge
-					let (x, y) = query_pointer(display, window);
-					if edge == in_edge(x, y, xmax, ymax, offset) {
-						run(&opts, edge, &cmds);
-					}
-				}
+		assert_eq!(resolve_command(&bindings, Some("HDMI-1"), &Edge::TOPLEFT), &Some("laptop-menu".to_string()));
+	}
 
-				oldx = x;
-				oldy = y;
-			}
+	#[test]
+	fn resolve_command_falls_back_to_global_when_monitor_unbound()
+	{
+		let mut monitors = HashMap::new();
+		monitors.insert("hdmi-1".to_string(), Commands { topleft: Some("laptop-menu".to_string()), ..Default::default() });
+		let bindings = Bindings { global: Commands { topright: Some("global-menu".to_string()), ..Default::default() }, monitors };
 
-			xlib::XFreeEventData(display, &mut cookie);
+		assert_eq!(resolve_command(&bindings, Some("HDMI-1"), &Edge::TOPRIGHT), &Some("global-menu".to_string()));
+	}
 
-			// Wait for events
-			if libc::poll(&mut fds, 1, -1) < 0 {
-				panic!("poll failed");
-			}
-		};
+	#[test]
+	fn resolve_command_falls_back_to_global_when_no_monitor()
+	{
+		let bindings = Bindings { global: Commands { topleft: Some("global-menu".to_string()), ..Default::default() }, monitors: HashMap::new() };
 
-		// Clean up
-		xrandr::XRRFreeMonitors(monitorinfo);
-		xlib::XCloseDisplay(display);
+		assert_eq!(resolve_command(&bindings, None, &Edge::TOPLEFT), &Some("global-menu".to_string()));
 	}
 }
@@ -0,0 +1,447 @@
+//! Wayland backend, selected at runtime when `WAYLAND_DISPLAY` is set.
+//!
+//! There is no portable way to ask a Wayland compositor for the global
+//! pointer position, so instead we create thin, visually invisible
+//! layer-shell surfaces (via the wlr-layer-shell protocol) along the top,
+//! bottom, left and right edges of every output, each spanning the whole
+//! edge (corners included) and as thick as the larger of the configured
+//! `corner_size`/`edge_margin` trigger sizes, and track `wl_pointer`
+//! enter/motion events on them. These strips deliberately keep the
+//! compositor's default (opaque) input region rather than setting an
+//! empty one: `wl_pointer` only reports enter/motion for a surface the
+//! pointer is actually over, so an input-transparent surface would never
+//! receive the events this backend depends on. Unlike covering the whole
+//! output, sizing the strips to the trigger geometry means the overlay
+//! only intercepts clicks within these border strips, not the rest of the
+//! desktop; the strips also cover the stretches between corner and edge
+//! zones and edges with no command bound. `set_keyboard_interactivity(None)`
+//! at least keeps it out of the way of keyboard focus. Each strip's
+//! on-screen position comes from xdg-output, which turns local surface
+//! coordinates into the same kind of absolute, per-monitor bounds
+//! `in_edge`/`run` already expect from the X11 backend.
+
+use std::error::Error;
+use std::ffi::CString;
+use std::os::unix::io::{FromRawFd, OwnedFd};
+use std::sync::atomic::Ordering;
+
+use wayland_client::protocol::{wl_buffer, wl_compositor, wl_output, wl_pointer, wl_registry, wl_seat, wl_shm, wl_shm_pool, wl_surface};
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols::xdg::xdg_output::zv1::client::{zxdg_output_manager_v1, zxdg_output_v1};
+use wayland_protocols_wlr::layer_shell::v1::client::{zwlr_layer_shell_v1, zwlr_layer_surface_v1};
+
+use crate::{PointerSource, PointerUpdate, RELOAD, RUNNING};
+
+struct Output {
+	wl_output: wl_output::WlOutput,
+	x: i32,
+	y: i32,
+	width: i32,
+	height: i32,
+	// wl_output's integer HiDPI scale, the compositor's own toolkit factor
+	scale: i32,
+	// xdg-output's name, e.g. "HDMI-1", for matching `[monitor.NAME]` sections
+	name: Option<String>,
+}
+
+/// Which corner/edge of an output a trigger strip covers. Determines both
+/// the strip's layer-shell anchor and how its surface-local coordinates
+/// map back into the output's absolute logical space.
+#[derive(Clone, Copy)]
+enum ZoneKind {
+	Top,
+	Bottom,
+	Left,
+	Right,
+}
+
+/// A single trigger-strip surface anchored to one edge of one output.
+struct Zone {
+	surface: wl_surface::WlSurface,
+	output_index: usize,
+	kind: ZoneKind,
+}
+
+#[derive(Default)]
+struct State {
+	compositor: Option<wl_compositor::WlCompositor>,
+	shm: Option<wl_shm::WlShm>,
+	layer_shell: Option<zwlr_layer_shell_v1::ZwlrLayerShellV1>,
+	output_manager: Option<zxdg_output_manager_v1::ZxdgOutputManagerV1>,
+	outputs: Vec<Output>,
+	zones: Vec<Zone>,
+	// Trigger strip thickness in logical pixels, before per-output DPI scaling
+	margin: i32,
+	focused: Option<usize>,
+	last_position: Option<PointerUpdate>,
+	motion: Option<PointerUpdate>,
+}
+
+impl State {
+	fn zone_index(&self, surface: &wl_surface::WlSurface) -> Option<usize>
+	{
+		self.zones.iter().position(|z| &z.surface == surface)
+	}
+}
+
+delegate_noop!(State: ignore wl_compositor::WlCompositor);
+delegate_noop!(State: ignore wl_shm::WlShm);
+delegate_noop!(State: ignore wl_shm_pool::WlShmPool);
+delegate_noop!(State: ignore wl_buffer::WlBuffer);
+delegate_noop!(State: ignore wl_surface::WlSurface);
+delegate_noop!(State: ignore zwlr_layer_shell_v1::ZwlrLayerShellV1);
+delegate_noop!(State: ignore zxdg_output_manager_v1::ZxdgOutputManagerV1);
+
+impl Dispatch<wl_registry::WlRegistry, ()> for State {
+	fn event(state: &mut Self, registry: &wl_registry::WlRegistry, event: wl_registry::Event, _: &(), _: &Connection, qh: &QueueHandle<Self>)
+	{
+		if let wl_registry::Event::Global { name, interface, version } = event {
+			match interface.as_str() {
+				"wl_compositor" => {
+					state.compositor = Some(registry.bind(name, version.min(4), qh, ()));
+				}
+				"wl_shm" => {
+					state.shm = Some(registry.bind(name, version.min(1), qh, ()));
+				}
+				"wl_seat" => {
+					let _seat: wl_seat::WlSeat = registry.bind(name, version.min(7), qh, ());
+				}
+				"wl_output" => {
+					let wl_output: wl_output::WlOutput = registry.bind(name, version.min(3), qh, ());
+					state.outputs.push(Output::new(wl_output));
+				}
+				"zwlr_layer_shell_v1" => {
+					state.layer_shell = Some(registry.bind(name, version.min(4), qh, ()));
+				}
+				"zxdg_output_manager_v1" => {
+					state.output_manager = Some(registry.bind(name, version.min(3), qh, ()));
+				}
+				_ => {}
+			}
+		}
+	}
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for State {
+	fn event(_: &mut Self, seat: &wl_seat::WlSeat, event: wl_seat::Event, _: &(), _: &Connection, qh: &QueueHandle<Self>)
+	{
+		if let wl_seat::Event::Capabilities { capabilities } = event {
+			if capabilities.contains(wl_seat::Capability::Pointer) {
+				let _pointer: wl_pointer::WlPointer = seat.get_pointer(qh, ());
+			}
+		}
+	}
+}
+
+impl Dispatch<wl_pointer::WlPointer, ()> for State {
+	fn event(state: &mut Self, _: &wl_pointer::WlPointer, event: wl_pointer::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+	{
+		match event {
+			wl_pointer::Event::Enter { surface, surface_x, surface_y, .. } => {
+				state.focused = state.zone_index(&surface);
+				state.record_motion(surface_x, surface_y);
+			}
+			wl_pointer::Event::Motion { surface_x, surface_y, .. } => {
+				state.record_motion(surface_x, surface_y);
+			}
+			wl_pointer::Event::Leave { .. } => {
+				state.focused = None;
+			}
+			_ => {}
+		}
+	}
+}
+
+impl Dispatch<wl_output::WlOutput, ()> for State {
+	fn event(state: &mut Self, wl_output: &wl_output::WlOutput, event: wl_output::Event, _: &(), _: &Connection, _: &QueueHandle<Self>)
+	{
+		if let wl_output::Event::Scale { factor } = event {
+			if let Some(output) = state.outputs.iter_mut().find(|o| &o.wl_output == wl_output) {
+				output.scale = factor;
+			}
+		}
+	}
+}
+
+impl Dispatch<zxdg_output_v1::ZxdgOutputV1, usize> for State {
+	fn event(state: &mut Self, _: &zxdg_output_v1::ZxdgOutputV1, event: zxdg_output_v1::Event, index: &usize, _: &Connection, _: &QueueHandle<Self>)
+	{
+		let output = &mut state.outputs[*index];
+		match event {
+			zxdg_output_v1::Event::LogicalPosition { x, y } => {
+				output.x = x;
+				output.y = y;
+			}
+			zxdg_output_v1::Event::LogicalSize { width, height } => {
+				output.width = width;
+				output.height = height;
+			}
+			zxdg_output_v1::Event::Name { name } => {
+				output.name = Some(name);
+			}
+			_ => {}
+		}
+	}
+}
+
+impl Dispatch<zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, usize> for State {
+	fn event(state: &mut Self, layer_surface: &zwlr_layer_surface_v1::ZwlrLayerSurfaceV1, event: zwlr_layer_surface_v1::Event, index: &usize, _: &Connection, qh: &QueueHandle<Self>)
+	{
+		if let zwlr_layer_surface_v1::Event::Configure { serial, width, height } = event {
+			layer_surface.ack_configure(serial);
+
+			let zone = &state.zones[*index];
+			// A layer surface with no committed buffer is never mapped,
+			// and an unmapped surface never receives wl_pointer events.
+			// Attach a fully transparent buffer sized to what the
+			// compositor just told us it allocated.
+			if let Some(shm) = &state.shm {
+				if let Ok(buffer) = create_transparent_buffer(shm, width as i32, height as i32, qh) {
+					zone.surface.attach(Some(&buffer), 0, 0);
+					zone.surface.damage_buffer(0, 0, width as i32, height as i32);
+				}
+			}
+
+			zone.surface.commit();
+		}
+	}
+}
+
+impl Output {
+	fn new(wl_output: wl_output::WlOutput) -> Self
+	{
+		Output { wl_output, x: 0, y: 0, width: 0, height: 0, scale: 1, name: None }
+	}
+}
+
+/// Scales the configured logical-pixel strip thickness to an output's
+/// HiDPI factor, the same way `EdgeActor::on_motion` scales
+/// `corner_size`/`edge_margin` before comparing them against a pointer
+/// position on that output.
+fn zone_thickness(margin: i32, scale: i32) -> i32
+{
+	(margin * scale).max(1)
+}
+
+impl State {
+	fn record_motion(&mut self, surface_x: wayland_client::Fixed, surface_y: wayland_client::Fixed)
+	{
+		if let Some(i) = self.focused {
+			let zone = &self.zones[i];
+			let output = &self.outputs[zone.output_index];
+			let thickness = zone_thickness(self.margin, output.scale);
+
+			// A zone surface's local (0, 0) is that surface's own
+			// top-left corner, which only coincides with the output's
+			// top-left when the zone is anchored there; bottom/right
+			// strips are offset by the output size minus the strip
+			// thickness.
+			let (origin_x, origin_y) = match zone.kind {
+				ZoneKind::Top | ZoneKind::Left => (output.x, output.y),
+				ZoneKind::Bottom => (output.x, output.y + output.height - thickness),
+				ZoneKind::Right => (output.x + output.width - thickness, output.y),
+			};
+
+			let x = origin_x + surface_x.as_f64().round() as i32;
+			let y = origin_y + surface_y.as_f64().round() as i32;
+			// xmax/ymax must live in the same absolute space as x/y, the
+			// same convention the X11 backend's get_xymax() uses.
+			let xmax = output.x + output.width - 1;
+			let ymax = output.y + output.height - 1;
+			let scale = output.scale as f64;
+			let monitor = output.name.clone();
+			self.last_position = Some(PointerUpdate { x, y, xmax, ymax, scale, monitor });
+			self.motion = self.last_position.clone();
+		}
+	}
+}
+
+/// Creates a fully transparent (zero-filled ARGB8888) `wl_buffer` of the
+/// given size via an anonymous `memfd`-backed `wl_shm` pool.
+fn create_transparent_buffer(shm: &wl_shm::WlShm, width: i32, height: i32, qh: &QueueHandle<State>) -> Result<wl_buffer::WlBuffer, Box<dyn Error>>
+{
+	let width = width.max(1);
+	let height = height.max(1);
+	let stride = width * 4;
+	let size = (stride * height) as usize;
+
+	let name = CString::new("edges-overlay").unwrap();
+
+	// SAFETY: memfd_create/ftruncate are called with valid arguments; the
+	// returned fd is owned exclusively by this function from here on.
+	let fd = unsafe {
+		let fd = libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC);
+		if fd < 0 {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		if libc::ftruncate(fd, size as libc::off_t) < 0 {
+			let err = std::io::Error::last_os_error();
+			libc::close(fd);
+			return Err(err.into());
+		}
+		OwnedFd::from_raw_fd(fd)
+	};
+
+	// A freshly truncated memfd reads back as all zeroes, which is a
+	// fully transparent pixel in ARGB8888 - no need to map and fill it.
+	let pool = shm.create_pool(fd, size as i32, qh, ());
+	let buffer = pool.create_buffer(0, width, height, stride, wl_shm::Format::Argb8888, qh, ());
+	pool.destroy();
+
+	Ok(buffer)
+}
+
+pub(crate) struct WaylandSource {
+	conn: Connection,
+	queue: EventQueue<State>,
+	state: State,
+}
+
+impl WaylandSource {
+	/// `margin` is the configured trigger geometry in logical pixels (the
+	/// larger of `corner_size`/`edge_margin`), used as the thickness of the
+	/// border strip surfaces.
+	pub(crate) fn new(margin: u32) -> Result<Self, Box<dyn Error>>
+	{
+		let conn = Connection::connect_to_env()?;
+		let display = conn.display();
+
+		let mut queue = conn.new_event_queue::<State>();
+		let qh = queue.handle();
+		display.get_registry(&qh, ());
+
+		let mut state = State { margin: margin.max(1) as i32, ..State::default() };
+
+		// Let the compositor advertise its globals and bind the ones we need
+		queue.roundtrip(&mut state)?;
+
+		let compositor = state.compositor.clone().ok_or("compositor missing wl_compositor")?;
+		if state.shm.is_none() {
+			return Err("compositor missing wl_shm".into());
+		}
+		let layer_shell = state.layer_shell.clone().ok_or("compositor missing zwlr_layer_shell_v1")?;
+		let output_manager = state.output_manager.clone().ok_or("compositor missing zxdg_output_manager_v1")?;
+
+		// The outputs were only bound during the first roundtrip; wait for
+		// their wl_output events so each strip is sized with its output's
+		// actual scale rather than the default of 1
+		queue.roundtrip(&mut state)?;
+
+		// Create one trigger strip per edge of every output,
+		// rather than a single surface covering the whole output: see
+		// the module doc comment for why this bounds how much of the
+		// desktop the overlay can intercept clicks on.
+		for i in 0..state.outputs.len() {
+			let wl_output = state.outputs[i].wl_output.clone();
+			output_manager.get_xdg_output(&wl_output, &qh, i);
+
+			for kind in [ZoneKind::Top, ZoneKind::Bottom, ZoneKind::Left, ZoneKind::Right] {
+				let zone_index = state.zones.len();
+
+				let surface = compositor.create_surface(&qh, ());
+				let layer_surface = layer_shell.get_layer_surface(
+					&surface,
+					Some(&wl_output),
+					zwlr_layer_shell_v1::Layer::Overlay,
+					"edges".to_string(),
+					&qh,
+					zone_index,
+				);
+
+				let thickness = zone_thickness(state.margin, state.outputs[i].scale);
+				match kind {
+					ZoneKind::Top => {
+						layer_surface.set_anchor(zwlr_layer_surface_v1::Anchor::Top | zwlr_layer_surface_v1::Anchor::Left | zwlr_layer_surface_v1::Anchor::Right);
+						layer_surface.set_size(0, thickness as u32);
+					}
+					ZoneKind::Bottom => {
+						layer_surface.set_anchor(zwlr_layer_surface_v1::Anchor::Bottom | zwlr_layer_surface_v1::Anchor::Left | zwlr_layer_surface_v1::Anchor::Right);
+						layer_surface.set_size(0, thickness as u32);
+					}
+					ZoneKind::Left => {
+						layer_surface.set_anchor(zwlr_layer_surface_v1::Anchor::Left | zwlr_layer_surface_v1::Anchor::Top | zwlr_layer_surface_v1::Anchor::Bottom);
+						layer_surface.set_size(thickness as u32, 0);
+					}
+					ZoneKind::Right => {
+						layer_surface.set_anchor(zwlr_layer_surface_v1::Anchor::Right | zwlr_layer_surface_v1::Anchor::Top | zwlr_layer_surface_v1::Anchor::Bottom);
+						layer_surface.set_size(thickness as u32, 0);
+					}
+				}
+				layer_surface.set_exclusive_zone(-1);
+				layer_surface.set_keyboard_interactivity(zwlr_layer_surface_v1::KeyboardInteractivity::None);
+				surface.commit();
+
+				state.zones.push(Zone { surface, output_index: i, kind });
+			}
+		}
+
+		// Pick up the xdg-output geometry and each strip's initial
+		// layer-surface configure (which attaches its buffer)
+		queue.roundtrip(&mut state)?;
+
+		Ok(WaylandSource { conn, queue, state })
+	}
+}
+
+impl PointerSource for WaylandSource {
+	fn next_motion(&mut self) -> Result<Option<PointerUpdate>, Box<dyn Error>>
+	{
+		while RUNNING.load(Ordering::Relaxed) && !RELOAD.load(Ordering::Relaxed) {
+			self.state.motion = None;
+
+			if let Err(err) = self.queue.blocking_dispatch(&mut self.state) {
+				// A caught signal (e.g. SIGHUP asking for a config
+				// reload) interrupts the underlying poll(); let the
+				// caller re-check RUNNING/RELOAD instead of treating
+				// it as a hard failure, mirroring the X11 backend.
+				if is_interrupted(&err) {
+					continue;
+				}
+				if !RUNNING.load(Ordering::Relaxed) {
+					return Ok(None);
+				}
+				return Err(Box::new(err));
+			}
+
+			if let Some(motion) = self.state.motion.take() {
+				return Ok(Some(motion));
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Returns the last position recorded by `next_motion`. Events that
+	/// arrive while the caller sleeps are not dispatched here, so after a
+	/// delay this is still the position from before it.
+	fn query_pointer(&self) -> Result<(i32, i32), Box<dyn Error>>
+	{
+		match &self.state.last_position {
+			Some(update) => Ok((update.x, update.y)),
+			None => Err("no pointer position observed yet".into()),
+		}
+	}
+}
+
+/// Walks a dispatch error's `source()` chain looking for an interrupted
+/// syscall, the same signal-interruption case the X11 backend handles
+/// around its `poll()` call.
+fn is_interrupted(err: &(dyn Error + 'static)) -> bool
+{
+	let mut source = Some(err);
+	while let Some(err) = source {
+		if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
+			return io_err.kind() == std::io::ErrorKind::Interrupted;
+		}
+		source = err.source();
+	}
+
+	false
+}
+
+impl Drop for WaylandSource {
+	fn drop(&mut self)
+	{
+		let _ = self.conn.flush();
+	}
+}
@@ -0,0 +1,216 @@
+//! X11 backend (XInput2/RandR over an x11rb XCB connection), the default
+//! `PointerSource` on X11 sessions.
+
+use std::error::Error;
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::Ordering;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::{self, ConnectionExt as _};
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, Window};
+use x11rb::protocol::Event;
+use x11rb::xcb_ffi::XCBConnection;
+
+use crate::{PointerSource, PointerUpdate, RELOAD, RUNNING};
+
+pub(crate) struct X11Source {
+	conn: XCBConnection,
+	screen_num: usize,
+	window: Window,
+	monitors: Vec<randr::MonitorInfo>,
+}
+
+fn pointer_in_monitor(x: i32, y: i32, monitors: &[randr::MonitorInfo]) -> i32
+{
+	for (i, monitor) in monitors.iter().enumerate() {
+		let rect = (monitor.x as i32, monitor.y as i32, monitor.width as i32, monitor.height as i32);
+
+		if crate::point_in_rect(x, y, rect) {
+			return i as i32;
+		}
+	}
+
+	return -1;
+}
+
+/// Standard "logical pixel" baseline toolkits scale HiDPI factors against.
+const BASELINE_DPI: f64 = 96.0;
+
+/// Derives a HiDPI scale factor from a monitor's physical size in
+/// millimeters versus its pixel size, the same math toolkits use to turn
+/// RandR geometry into a scale factor. Returns `None` for monitors that
+/// don't report a physical size (e.g. some virtual outputs).
+fn monitor_scale(monitor: &randr::MonitorInfo) -> Option<f64>
+{
+	if monitor.width_in_millimeters == 0 {
+		return None;
+	}
+
+	let dpi = monitor.width as f64 / (monitor.width_in_millimeters as f64 / 25.4);
+	Some(dpi / BASELINE_DPI)
+}
+
+/// Falls back to the `Xft.dpi` X resource when a monitor doesn't report a
+/// usable physical size.
+fn query_xft_dpi(conn: &XCBConnection, window: Window) -> Option<f64>
+{
+	let atom = conn.intern_atom(false, b"RESOURCE_MANAGER").ok()?.reply().ok()?.atom;
+	let prop = conn.get_property(false, window, atom, AtomEnum::STRING.into(), 0, u32::MAX).ok()?.reply().ok()?;
+	let text = String::from_utf8(prop.value).ok()?;
+
+	for line in text.lines() {
+		if let Some(rest) = line.strip_prefix("Xft.dpi:") {
+			return rest.trim().parse::<f64>().ok();
+		}
+	}
+
+	None
+}
+
+/// Resolves a RandR monitor name atom (e.g. `HDMI-1`) to a string, for
+/// matching `[monitor.NAME]` config sections.
+fn monitor_name(conn: &XCBConnection, monitor: &randr::MonitorInfo) -> Option<String>
+{
+	let reply = conn.get_atom_name(monitor.name).ok()?.reply().ok()?;
+	String::from_utf8(reply.name).ok()
+}
+
+fn get_xymax(x: i32, y: i32, conn: &XCBConnection, window: Window, screen_num: usize, monitors: &[randr::MonitorInfo]) -> Result<PointerUpdate, Box<dyn Error>>
+{
+	let screen = &conn.setup().roots[screen_num];
+	let mut xmax = screen.width_in_pixels as i32 - 1;
+	let mut ymax = screen.height_in_pixels as i32 - 1;
+
+	let monitor = if monitors.len() == 1 {
+		&monitors[0]
+	} else {
+		let i = pointer_in_monitor(x, y, monitors);
+		if i < 0 {
+			return Err("pointer_in_monitor failed".into());
+		}
+
+		let monitor = &monitors[i as usize];
+
+		let w = monitor.width as i32;
+		let xoff = monitor.x as i32;
+		if xoff + w <= xmax {
+			xmax = xoff + w - 1;
+		}
+
+		let h = monitor.height as i32;
+		let yoff = monitor.y as i32;
+		if yoff + h <= ymax {
+			ymax = yoff + h - 1;
+		}
+
+		monitor
+	};
+
+	let scale = monitor_scale(monitor)
+		.or_else(|| query_xft_dpi(conn, window).map(|dpi| dpi / BASELINE_DPI))
+		.unwrap_or(1.0);
+
+	Ok(PointerUpdate { x, y, xmax, ymax, scale, monitor: monitor_name(conn, monitor) })
+}
+
+fn query_pointer(conn: &XCBConnection, window: Window) -> Result<(i32, i32), Box<dyn Error>>
+{
+	let pointer = conn.query_pointer(window)?.reply()?;
+
+	Ok((pointer.root_x as i32, pointer.root_y as i32))
+}
+
+fn get_monitors(conn: &XCBConnection, window: Window) -> Result<Vec<randr::MonitorInfo>, Box<dyn Error>>
+{
+	Ok(conn.randr_get_monitors(window, true)?.reply()?.monitors)
+}
+
+impl X11Source {
+	pub(crate) fn new() -> Result<Self, Box<dyn Error>>
+	{
+		// Connect to the X server
+		let (conn, screen_num) = XCBConnection::connect(None)?;
+		let window = conn.setup().roots[screen_num].root;
+
+		// Negotiate XInput2
+		let xi_version = conn.xinput_xi_query_version(2, 2)?.reply()?;
+		if xi_version.major_version < 2 {
+			return Err("XInput >= 2.0 not available".into());
+		}
+
+		// Negotiate Xrandr
+		let randr_version = conn.randr_query_version(1, 5)?.reply()?;
+		if randr_version.major_version < 1 || (randr_version.major_version == 1 && randr_version.minor_version < 5) {
+			return Err("Xrandr >= 1.5 not available".into());
+		}
+
+		// Select raw motion events
+		conn.xinput_xi_select_events(window, &[xinput::EventMask {
+			deviceid: xinput::Device::AllMaster.into(),
+			mask: vec![xinput::XIEventMask::RAW_MOTION],
+		}])?;
+
+		// React to monitor hotplug and resolution changes
+		conn.randr_select_input(window, randr::NotifyMask::SCREEN_CHANGE
+					 | randr::NotifyMask::OUTPUT_CHANGE
+					 | randr::NotifyMask::CRTC_CHANGE)?;
+
+		conn.flush()?;
+
+		// Get monitors
+		let monitors = get_monitors(&conn, window)?;
+
+		Ok(X11Source { conn, screen_num, window, monitors })
+	}
+}
+
+impl PointerSource for X11Source {
+	fn next_motion(&mut self) -> Result<Option<PointerUpdate>, Box<dyn Error>>
+	{
+		while RUNNING.load(Ordering::Relaxed) && !RELOAD.load(Ordering::Relaxed) {
+			let event = match self.conn.poll_for_event()? {
+				Some(event) => event,
+				None => {
+					let mut fds = libc::pollfd {
+						fd: self.conn.as_raw_fd(),
+						events: libc::POLLIN,
+						revents: 0,
+					};
+
+					if unsafe { libc::poll(&mut fds, 1, -1) } < 0 {
+						// A caught signal (e.g. SIGHUP asking for a
+						// config reload) interrupts poll(); let the
+						// caller re-check RUNNING/RELOAD instead of
+						// treating it as a hard failure.
+						if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+							continue;
+						}
+						return Err("poll failed".into());
+					}
+					continue;
+				}
+			};
+
+			// Re-fetch monitors on hotplug/resolution changes
+			if let Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_) = event {
+				self.monitors = get_monitors(&self.conn, self.window)?;
+				continue;
+			}
+
+			// Was pointer moved?
+			if let Event::XinputRawMotion(_) = event {
+				let (x, y) = query_pointer(&self.conn, self.window)?;
+
+				return Ok(Some(get_xymax(x, y, &self.conn, self.window, self.screen_num, &self.monitors)?));
+			}
+		}
+
+		Ok(None)
+	}
+
+	fn query_pointer(&self) -> Result<(i32, i32), Box<dyn Error>>
+	{
+		query_pointer(&self.conn, self.window)
+	}
+}